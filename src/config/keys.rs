@@ -0,0 +1,8 @@
+/// Actions shared across every pane, bound ahead of pane-specific keymaps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommonAction {
+    FocusInput,
+    Quit,
+    NextPane,
+    PrevPane,
+}
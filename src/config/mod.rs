@@ -0,0 +1,132 @@
+pub mod keys;
+
+use std::cell::Cell;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::shared::format::FormatPreset;
+
+/// Default for [`PersistedConfig::update_check_interval_days`], matching
+/// [`crate::shared::update::DEFAULT_UPDATE_INTERVAL`].
+const DEFAULT_UPDATE_CHECK_INTERVAL_DAYS: u64 = 14;
+
+fn default_update_check_interval_days() -> u64 {
+    DEFAULT_UPDATE_CHECK_INTERVAL_DAYS
+}
+
+/// On-disk shape of persisted settings, read/written as JSON under
+/// [`config_path`].
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedConfig {
+    #[serde(default)]
+    last_format_preset: FormatPreset,
+    /// How long the bootstrapped yt-dlp binary can go without a fetched
+    /// update before [`RuntimeConfig::update_check_interval`]'s staleness
+    /// check flags it, in days (stored as whole days rather than a
+    /// `Duration` so the on-disk JSON stays human-editable).
+    #[serde(default = "default_update_check_interval_days")]
+    update_check_interval_days: u64,
+}
+
+impl Default for PersistedConfig {
+    fn default() -> Self {
+        Self {
+            last_format_preset: FormatPreset::default(),
+            update_check_interval_days: DEFAULT_UPDATE_CHECK_INTERVAL_DAYS,
+        }
+    }
+}
+
+/// Directory TerminalMan stores its persisted settings file in, mirroring
+/// the `data_dir` pattern `shared::update` uses for the bootstrapped binary.
+fn config_dir() -> Result<PathBuf> {
+    let base = dirs::config_dir().context("could not determine platform config directory")?;
+    let dir = base.join("terminalman");
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    Ok(dir)
+}
+
+fn config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.json"))
+}
+
+/// App-wide settings that persist across sessions (threaded through
+/// [`crate::ctx::Ctx`] the same way [`crate::shared::ytdlp::YtDlpManager`] is).
+#[derive(Debug)]
+pub struct RuntimeConfig {
+    last_format_preset: Cell<FormatPreset>,
+    update_check_interval_days: Cell<u64>,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            last_format_preset: Cell::new(FormatPreset::default()),
+            update_check_interval_days: Cell::new(DEFAULT_UPDATE_CHECK_INTERVAL_DAYS),
+        }
+    }
+}
+
+impl RuntimeConfig {
+    /// Loads settings from [`config_path`], falling back to defaults if the
+    /// file is missing or unreadable (e.g. first run).
+    pub fn load() -> Self {
+        let persisted = config_path()
+            .ok()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice::<PersistedConfig>(&bytes).ok())
+            .unwrap_or_default();
+        Self {
+            last_format_preset: Cell::new(persisted.last_format_preset),
+            update_check_interval_days: Cell::new(persisted.update_check_interval_days.max(1)),
+        }
+    }
+
+    pub fn last_format_preset(&self) -> FormatPreset {
+        self.last_format_preset.get()
+    }
+
+    pub fn set_last_format_preset(&self, preset: FormatPreset) {
+        self.last_format_preset.set(preset);
+        self.save();
+    }
+
+    /// How long the bootstrapped yt-dlp binary can go without a fetched
+    /// update before it's flagged stale, per [`set_update_check_interval_days`].
+    ///
+    /// [`set_update_check_interval_days`]: Self::set_update_check_interval_days
+    pub fn update_check_interval(&self) -> Duration {
+        Duration::from_secs(self.update_check_interval_days.get() * 24 * 60 * 60)
+    }
+
+    pub fn update_check_interval_days(&self) -> u64 {
+        self.update_check_interval_days.get()
+    }
+
+    /// Adjusts the staleness interval at runtime, clamped to at least one
+    /// day so it can't be zeroed into flagging every startup as stale.
+    pub fn set_update_check_interval_days(&self, days: u64) {
+        self.update_check_interval_days.set(days.max(1));
+        self.save();
+    }
+
+    /// Writes current settings to disk; failures are swallowed since
+    /// persistence is a convenience (e.g. a read-only config dir shouldn't
+    /// break format-preset switching for the running session).
+    fn save(&self) {
+        let persisted = PersistedConfig {
+            last_format_preset: self.last_format_preset.get(),
+            update_check_interval_days: self.update_check_interval_days.get(),
+        };
+        let Ok(path) = config_path() else {
+            return;
+        };
+        if let Ok(json) = serde_json::to_vec_pretty(&persisted) {
+            let _ = fs::write(path, json);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+
+use crate::{
+    config::RuntimeConfig,
+    shared::{spotdl::SpotDlManager, ytdlp::YtDlpManager},
+    ui::input::InputManager,
+};
+
+/// Shared state threaded through every pane's render/handle_action calls.
+pub struct Ctx {
+    pub input: InputManager,
+    pub ytdlp_manager: YtDlpManager,
+    pub spotdl_manager: SpotDlManager,
+    pub config: RuntimeConfig,
+    redraw: bool,
+}
+
+impl Ctx {
+    pub fn new(ytdlp_manager: YtDlpManager, spotdl_manager: SpotDlManager) -> Self {
+        Self {
+            input: InputManager::default(),
+            ytdlp_manager,
+            spotdl_manager,
+            config: RuntimeConfig::load(),
+            redraw: false,
+        }
+    }
+
+    /// Marks the frame dirty so the next tick repaints immediately instead of
+    /// waiting for the poll interval.
+    pub fn render(&mut self) -> Result<()> {
+        self.redraw = true;
+        Ok(())
+    }
+
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.redraw)
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+/// A small set of yt-dlp format/quality presets the user can cycle through
+/// in [`crate::ui::panes::downloader::DownloaderPane`] before starting a
+/// download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FormatPreset {
+    BestAudioM4a,
+    BestAudioOpus,
+    BestVideo,
+    Res1080p,
+    AudioOnlyMp3,
+}
+
+impl FormatPreset {
+    pub const ALL: [FormatPreset; 5] = [
+        FormatPreset::BestAudioM4a,
+        FormatPreset::BestAudioOpus,
+        FormatPreset::BestVideo,
+        FormatPreset::Res1080p,
+        FormatPreset::AudioOnlyMp3,
+    ];
+
+    /// Short label shown next to the input box.
+    pub fn label(&self) -> &'static str {
+        match self {
+            FormatPreset::BestAudioM4a => "BestAudio-m4a",
+            FormatPreset::BestAudioOpus => "BestAudio-opus",
+            FormatPreset::BestVideo => "BestVideo",
+            FormatPreset::Res1080p => "1080p",
+            FormatPreset::AudioOnlyMp3 => "AudioOnly-mp3",
+        }
+    }
+
+    /// Rotates to the next preset, wrapping back to the first.
+    pub fn next(self) -> Self {
+        let idx = Self::ALL.iter().position(|p| *p == self).expect("self is in ALL");
+        Self::ALL[(idx + 1) % Self::ALL.len()]
+    }
+
+    /// yt-dlp CLI flags that select this preset.
+    pub fn yt_dlp_args(&self) -> Vec<String> {
+        match self {
+            FormatPreset::BestAudioM4a => {
+                vec!["-x".to_string(), "--audio-format".to_string(), "m4a".to_string()]
+            }
+            FormatPreset::BestAudioOpus => {
+                vec!["-x".to_string(), "--audio-format".to_string(), "opus".to_string()]
+            }
+            FormatPreset::BestVideo => vec!["-f".to_string(), "bestvideo+bestaudio".to_string()],
+            FormatPreset::Res1080p => {
+                vec!["-f".to_string(), "bestvideo[height<=1080]+bestaudio".to_string()]
+            }
+            FormatPreset::AudioOnlyMp3 => {
+                vec!["-x".to_string(), "--audio-format".to_string(), "mp3".to_string()]
+            }
+        }
+    }
+}
+
+impl Default for FormatPreset {
+    fn default() -> Self {
+        FormatPreset::BestAudioM4a
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_cycles_through_all_presets_in_order() {
+        let mut preset = FormatPreset::default();
+        for expected in &FormatPreset::ALL[1..] {
+            preset = preset.next();
+            assert_eq!(preset, *expected);
+        }
+    }
+
+    #[test]
+    fn next_wraps_back_to_the_first_preset() {
+        let last = *FormatPreset::ALL.last().unwrap();
+        assert_eq!(last.next(), FormatPreset::ALL[0]);
+    }
+
+    #[test]
+    fn yt_dlp_args_select_extraction_for_audio_presets() {
+        assert_eq!(
+            FormatPreset::BestAudioM4a.yt_dlp_args(),
+            vec!["-x".to_string(), "--audio-format".to_string(), "m4a".to_string()]
+        );
+        assert_eq!(
+            FormatPreset::AudioOnlyMp3.yt_dlp_args(),
+            vec!["-x".to_string(), "--audio-format".to_string(), "mp3".to_string()]
+        );
+    }
+
+    #[test]
+    fn yt_dlp_args_select_a_format_string_for_video_presets() {
+        assert_eq!(
+            FormatPreset::Res1080p.yt_dlp_args(),
+            vec!["-f".to_string(), "bestvideo[height<=1080]+bestaudio".to_string()]
+        );
+    }
+}
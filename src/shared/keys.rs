@@ -0,0 +1,52 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::config::keys::CommonAction;
+
+/// Wraps a raw key press as it flows through a pane's `handle_action`.
+///
+/// Panes call [`ActionEvent::claim_common`] first so shared bindings (focus,
+/// pane switching, quit) take priority; anything left unclaimed can be read
+/// back with [`ActionEvent::code`] for pane-local keymaps.
+#[derive(Debug, Clone, Copy)]
+pub struct ActionEvent {
+    key: KeyEvent,
+    claimed: bool,
+}
+
+impl ActionEvent {
+    pub fn new(key: KeyEvent) -> Self {
+        Self {
+            key,
+            claimed: false,
+        }
+    }
+
+    pub fn claim_common(&mut self) -> Option<CommonAction> {
+        if self.claimed {
+            return None;
+        }
+        let action = match (self.key.code, self.key.modifiers) {
+            (KeyCode::Char('i'), KeyModifiers::NONE) => Some(CommonAction::FocusInput),
+            (KeyCode::Char('q'), KeyModifiers::NONE) => Some(CommonAction::Quit),
+            (KeyCode::Tab, KeyModifiers::NONE) => Some(CommonAction::NextPane),
+            (KeyCode::BackTab, KeyModifiers::SHIFT) => Some(CommonAction::PrevPane),
+            _ => None,
+        };
+        if action.is_some() {
+            self.claimed = true;
+        }
+        action
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.key.code
+    }
+
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.key.modifiers
+    }
+
+    pub fn is_claimed(&self) -> bool {
+        self.claimed
+    }
+}
@@ -0,0 +1,26 @@
+#[macro_export]
+macro_rules! status_info {
+    ($($arg:tt)*) => {
+        $crate::shared::macros::push_status($crate::shared::macros::StatusLevel::Info, format!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! status_error {
+    ($($arg:tt)*) => {
+        $crate::shared::macros::push_status($crate::shared::macros::StatusLevel::Error, format!($($arg)*))
+    };
+}
+
+pub use crate::status_error;
+pub use crate::status_info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusLevel {
+    Info,
+    Error,
+}
+
+pub fn push_status(_level: StatusLevel, _message: String) {
+    // The message is dropped here; nothing in this function displays it.
+}
@@ -0,0 +1,7 @@
+pub mod format;
+pub mod keys;
+pub mod macros;
+pub mod process_manager;
+pub mod spotdl;
+pub mod update;
+pub mod ytdlp;
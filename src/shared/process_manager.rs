@@ -0,0 +1,503 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use uuid::Uuid;
+
+/// Default cap on concurrently running subprocesses, shared by every backend
+/// unless overridden via [`ProcessManager::set_max_concurrent`].
+pub const DEFAULT_MAX_CONCURRENT: usize = 3;
+
+/// Static details about a queued/running job, independent of its current state.
+///
+/// `title`/`uploader`/`duration`/`thumbnail` come from a metadata fetch and
+/// are `None` until that completes (or if it fails); `render` falls back to
+/// `id`/`kind` in that case.
+#[derive(Debug, Clone, Default)]
+pub struct DownloadMeta {
+    pub id: String,
+    pub kind: String,
+    pub title: Option<String>,
+    pub uploader: Option<String>,
+    pub duration: Option<f64>,
+    pub thumbnail: Option<String>,
+    pub playlist_count: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum DownloadState {
+    Queued,
+    Downloading {
+        started_at: SystemTime,
+        percent: f32,
+        speed: String,
+        eta: String,
+    },
+    Completed {
+        path: String,
+    },
+    AlreadyDownloaded {
+        path: String,
+    },
+    Failed {
+        logs: Vec<String>,
+    },
+    Canceled,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadItem {
+    pub inner: DownloadMeta,
+    pub state: DownloadState,
+}
+
+/// Folds a newly parsed `percent` into the `current` displayed percentage.
+///
+/// yt-dlp restarts its percentage from 0 when it moves from the video
+/// stream to the audio stream (or vice versa) in a merged download; only
+/// let it regress when it's clearly starting a new segment, otherwise clamp
+/// to non-decreasing so a stray low reading can't make the gauge jump back.
+pub(crate) fn clamp_segment_progress(current: f32, percent: f32) -> f32 {
+    let is_new_segment = percent < 1.0 && current > 90.0;
+    if is_new_segment {
+        percent
+    } else {
+        percent.max(current)
+    }
+}
+
+/// How a backend plugs its subprocess into [`ProcessManager`]'s shared
+/// queue/concurrency/cancel/requeue machinery. `Spec` is whatever a backend
+/// needs remembered per job to build its `Command` (and, for yt-dlp, to
+/// retry a metadata fetch on requeue).
+pub trait ProcessBackend<Spec>: Send + Sync {
+    /// Builds the subprocess to run for `spec`. `stdout`/`stderr` are piped
+    /// by the caller regardless of what's set here.
+    fn build_command(&self, spec: &Spec) -> Command;
+
+    /// Parses one line of the child's stdout into `(percent, speed, eta)`,
+    /// for backends with a line-oriented progress format. Returns `None` by
+    /// default, for backends (like spotdl) with no progress to parse.
+    fn parse_progress(&self, _line: &str) -> Option<(f32, String, String)> {
+        None
+    }
+
+    /// Runs before a scheduled job's process is spawned, still under the
+    /// concurrency permit `try_schedule` reserved for it — e.g. yt-dlp's
+    /// metadata fetch and playlist expansion. Identity by default.
+    fn prepare(&self, _manager: &ProcessManager<Spec>, _id: Uuid, spec: Spec) -> Spec {
+        spec
+    }
+
+    /// Turns a spawn failure into the error shown in the item's log viewer,
+    /// for backends with a friendlier message than the raw `io::Error` (e.g.
+    /// spotdl's "executable not found — install spotdl"). Passed through
+    /// as-is by default.
+    fn spawn_error(&self, error: std::io::Error) -> anyhow::Error {
+        anyhow::Error::from(error)
+    }
+}
+
+/// Drives subprocesses for one backend (yt-dlp, spotdl, ...) and tracks
+/// their [`DownloadItem`] state.
+///
+/// `enqueue` never spawns directly: it always queues, and
+/// [`ProcessManager::try_schedule`] promotes queued items to `Downloading`
+/// as permits under `max_concurrent` free up, so a long paste list doesn't
+/// fork an unbounded number of processes at once. `ProcessBackend::prepare`
+/// runs inside that same reserved permit, so a slow per-job setup step (like
+/// yt-dlp's `-J` metadata fetch) is bounded by `max_concurrent` too, not just
+/// the actual download.
+pub struct ProcessManager<Spec> {
+    label: &'static str,
+    items: Arc<Mutex<HashMap<Uuid, DownloadItem>>>,
+    /// Ids in the order items were first inserted into `items`, since
+    /// `HashMap` iteration order is neither insertion order nor stable
+    /// across insertions — `DownloaderPane` needs a stable row order to
+    /// keep its `selected` index pointed at the same item.
+    order: Arc<Mutex<Vec<Uuid>>>,
+    queue: Arc<Mutex<VecDeque<Uuid>>>,
+    /// Specs are kept even after a job is scheduled (never removed), so a
+    /// `Failed`/`Canceled` item can be requeued without the caller
+    /// re-supplying them.
+    pending: Arc<Mutex<HashMap<Uuid, Spec>>>,
+    /// Handles for jobs currently `Downloading`, so `cancel` can kill them.
+    children: Arc<Mutex<HashMap<Uuid, Arc<Mutex<std::process::Child>>>>>,
+    active: Arc<Mutex<usize>>,
+    max_concurrent: Arc<Mutex<usize>>,
+    backend: Arc<dyn ProcessBackend<Spec>>,
+}
+
+impl<Spec> Clone for ProcessManager<Spec> {
+    fn clone(&self) -> Self {
+        Self {
+            label: self.label,
+            items: self.items.clone(),
+            order: self.order.clone(),
+            queue: self.queue.clone(),
+            pending: self.pending.clone(),
+            children: self.children.clone(),
+            active: self.active.clone(),
+            max_concurrent: self.max_concurrent.clone(),
+            backend: self.backend.clone(),
+        }
+    }
+}
+
+impl<Spec: Clone + Send + 'static> ProcessManager<Spec> {
+    /// `label` identifies the backend in mutex-poisoned panic messages (e.g.
+    /// `"ytdlp"`, `"spotdl"`). `backend` is shared rather than owned so a
+    /// caller that also needs direct access to backend-specific state (like
+    /// `YtDlpManager` does for `exe_path`) can keep its own handle to it.
+    pub fn new(label: &'static str, backend: Arc<dyn ProcessBackend<Spec>>) -> Self {
+        Self {
+            label,
+            items: Arc::new(Mutex::new(HashMap::new())),
+            order: Arc::new(Mutex::new(Vec::new())),
+            queue: Arc::new(Mutex::new(VecDeque::new())),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            children: Arc::new(Mutex::new(HashMap::new())),
+            active: Arc::new(Mutex::new(0)),
+            max_concurrent: Arc::new(Mutex::new(DEFAULT_MAX_CONCURRENT)),
+            backend,
+        }
+    }
+
+    /// Current meta/state for one item, for the log viewer.
+    pub fn get(&self, id: Uuid) -> Option<DownloadItem> {
+        self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label)).get(&id).cloned()
+    }
+
+    /// Cancels a `Queued` or `Downloading` item: kills its child process (if
+    /// already spawned) or drops it from the queue, then marks it `Canceled`.
+    pub fn cancel(&self, id: Uuid) -> Result<()> {
+        if let Some(handle) = self.children.lock().unwrap_or_else(|_| panic!("{} children mutex poisoned", self.label)).get(&id).cloned() {
+            let _ = handle.lock().unwrap_or_else(|_| panic!("{} child mutex poisoned", self.label)).kill();
+        } else {
+            self.queue.lock().unwrap_or_else(|_| panic!("{} queue mutex poisoned", self.label)).retain(|queued| *queued != id);
+        }
+        let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        if let Some(item) = items.get_mut(&id) {
+            item.state = DownloadState::Canceled;
+        }
+        Ok(())
+    }
+
+    /// Requeues a `Failed` or `Canceled` item using its originally remembered spec.
+    pub fn requeue(&self, id: Uuid) -> Result<()> {
+        let still_known = self.pending.lock().unwrap_or_else(|_| panic!("{} pending mutex poisoned", self.label)).contains_key(&id);
+        anyhow::ensure!(still_known, "no remembered download spec for this item");
+
+        let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        let item = items.get_mut(&id).context("item no longer tracked")?;
+        anyhow::ensure!(
+            matches!(item.state, DownloadState::Failed { .. } | DownloadState::Canceled),
+            "only failed or canceled items can be requeued"
+        );
+        item.state = DownloadState::Queued;
+        drop(items);
+
+        self.queue.lock().unwrap_or_else(|_| panic!("{} queue mutex poisoned", self.label)).push_back(id);
+        self.try_schedule();
+        Ok(())
+    }
+
+    /// Snapshot of every tracked item alongside its id, in insertion order,
+    /// so a UI list can map a selected row back to the item to
+    /// `cancel`/`requeue`/`get` it.
+    pub fn snapshot(&self) -> Vec<(Uuid, DownloadItem)> {
+        let order = self.order.lock().unwrap_or_else(|_| panic!("{} order mutex poisoned", self.label));
+        let items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        order.iter().filter_map(|id| items.get(id).map(|item| (*id, item.clone()))).collect()
+    }
+
+    /// Number of processes currently running and still waiting in the queue,
+    /// for the "N active / M queued" status line.
+    pub fn counts(&self) -> (usize, usize) {
+        let active = *self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label));
+        let queued = self.queue.lock().unwrap_or_else(|_| panic!("{} queue mutex poisoned", self.label)).len();
+        (active, queued)
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        *self.max_concurrent.lock().unwrap_or_else(|_| panic!("{} max_concurrent mutex poisoned", self.label))
+    }
+
+    /// Adjusts the concurrency cap at runtime and immediately tries to
+    /// promote more queued items if it was raised.
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        *self.max_concurrent.lock().unwrap_or_else(|_| panic!("{} max_concurrent mutex poisoned", self.label)) = max_concurrent.max(1);
+        self.try_schedule();
+    }
+
+    /// Inserts `meta` as a bare `Queued` item remembering `spec` for a later
+    /// requeue, and adds it to the scheduling queue. Returns the new item's id.
+    pub fn enqueue(&self, spec: Spec, meta: DownloadMeta) -> Uuid {
+        let id = Uuid::new_v4();
+        {
+            let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+            items.insert(id, DownloadItem { inner: meta, state: DownloadState::Queued });
+        }
+        self.order.lock().unwrap_or_else(|_| panic!("{} order mutex poisoned", self.label)).push(id);
+        self.pending.lock().unwrap_or_else(|_| panic!("{} pending mutex poisoned", self.label)).insert(id, spec);
+        self.queue.lock().unwrap_or_else(|_| panic!("{} queue mutex poisoned", self.label)).push_back(id);
+
+        self.try_schedule();
+        id
+    }
+
+    /// Overwrites an already-inserted item's displayed metadata in place,
+    /// once e.g. a metadata fetch for it resolves.
+    pub fn patch_meta(&self, id: Uuid, meta: DownloadMeta) {
+        let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        if let Some(item) = items.get_mut(&id) {
+            item.inner = meta;
+        }
+    }
+
+    /// Inserts `item` under a fresh id without adding it to the scheduling
+    /// queue, for pseudo-items a backend drives entirely itself outside the
+    /// queue/cancel/requeue flow (e.g. yt-dlp's self-update fetch). Still
+    /// visible to `get`/`snapshot`, so it renders alongside real downloads.
+    pub fn insert_untracked(&self, item: DownloadItem) -> Uuid {
+        let id = Uuid::new_v4();
+        self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label)).insert(id, item);
+        self.order.lock().unwrap_or_else(|_| panic!("{} order mutex poisoned", self.label)).push(id);
+        id
+    }
+
+    /// Overwrites `id`'s displayed `percent`, leaving `started_at`/`speed`/
+    /// `eta` as they were. No-op if `id` isn't currently `Downloading`. For
+    /// an [`Self::insert_untracked`] pseudo-item driving its own progress
+    /// outside [`Self::spawn_process`] (e.g. a plain byte-count download).
+    pub fn update_progress(&self, id: Uuid, percent: f32) {
+        let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        if let Some(item) = items.get_mut(&id) {
+            if let DownloadState::Downloading { started_at, speed, eta, .. } = &item.state {
+                item.state = DownloadState::Downloading {
+                    started_at: *started_at,
+                    percent,
+                    speed: speed.clone(),
+                    eta: eta.clone(),
+                };
+            }
+        }
+    }
+
+    /// Overwrites `id`'s state outright, for a pseudo-item's terminal
+    /// transition (`Completed`/`Failed`) outside [`Self::spawn_process`].
+    pub fn set_state(&self, id: Uuid, state: DownloadState) {
+        let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+        if let Some(item) = items.get_mut(&id) {
+            item.state = state;
+        }
+    }
+
+    /// Promotes queued items to `Downloading` while permits are available.
+    /// Each promoted item prepares and spawns its process on its own
+    /// background thread ([`Self::run_scheduled`]), so a slow per-job
+    /// `prepare` step for one item can't stall scheduling of the others.
+    fn try_schedule(&self) {
+        loop {
+            // Check-and-increment must happen under a single lock acquisition:
+            // `try_schedule` runs concurrently from several call sites (a
+            // finishing job, `enqueue`, `requeue`, `set_max_concurrent`), and
+            // releasing the lock between the check and the increment lets two
+            // threads both pass the check before either increments, spawning
+            // more than `max_concurrent` processes at once.
+            {
+                let mut active = self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label));
+                if *active >= self.max_concurrent() {
+                    return;
+                }
+                *active += 1;
+            }
+            let next = self.queue.lock().unwrap_or_else(|_| panic!("{} queue mutex poisoned", self.label)).pop_front();
+            let Some(id) = next else {
+                *self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label)) -= 1;
+                return;
+            };
+            let Some(spec) = self.pending.lock().unwrap_or_else(|_| panic!("{} pending mutex poisoned", self.label)).get(&id).cloned() else {
+                *self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label)) -= 1;
+                continue;
+            };
+            let manager = self.clone();
+            std::thread::spawn(move || manager.run_scheduled(id, spec));
+        }
+    }
+
+    /// Prepares `id`'s spec and spawns its process, both still under the
+    /// permit `try_schedule` reserved for it before spawning this thread.
+    fn run_scheduled(&self, id: Uuid, spec: Spec) {
+        let spec = self.backend.prepare(self, id, spec);
+        self.pending.lock().unwrap_or_else(|_| panic!("{} pending mutex poisoned", self.label)).insert(id, spec.clone());
+        if let Err(e) = self.spawn_process(id, spec) {
+            let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+            if let Some(item) = items.get_mut(&id) {
+                item.state = DownloadState::Failed { logs: vec![e.to_string()] };
+            }
+            drop(items);
+            *self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label)) -= 1;
+            self.try_schedule();
+        }
+    }
+
+    /// Spawns the backend's subprocess for an item that just left the queue
+    /// and streams its progress into `items` until it exits.
+    fn spawn_process(&self, id: Uuid, spec: Spec) -> Result<()> {
+        {
+            let items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+            let canceled = matches!(
+                items.get(&id).map(|item| &item.state),
+                Some(DownloadState::Canceled)
+            );
+            drop(items);
+            // An item stays `Queued` (not `Downloading`) for the whole
+            // duration of `backend.prepare()` — the yt-dlp metadata fetch,
+            // or the first-run binary bootstrap — so it's still legal to
+            // `cancel` while this function is being called for it. Spawning
+            // the real process anyway would leave it running with nothing
+            // in `children` pointing at it once a later `requeue` overwrites
+            // this id's entry, permanently orphaning the (now unkillable)
+            // child. Bail out before `cmd.spawn()` instead, releasing the
+            // permit `try_schedule` reserved for it.
+            if canceled {
+                *self.active.lock().unwrap_or_else(|_| panic!("{} active mutex poisoned", self.label)) -= 1;
+                self.try_schedule();
+                return Ok(());
+            }
+        }
+        {
+            let mut items = self.items.lock().unwrap_or_else(|_| panic!("{} items mutex poisoned", self.label));
+            if let Some(item) = items.get_mut(&id) {
+                // `cancel` may have already set this to `Canceled` between
+                // the check above and this write; don't resurrect it as
+                // `Downloading` with no child registered.
+                if !matches!(item.state, DownloadState::Canceled) {
+                    item.state = DownloadState::Downloading {
+                        started_at: SystemTime::now(),
+                        percent: 0.0,
+                        speed: String::new(),
+                        eta: String::new(),
+                    };
+                }
+            }
+        }
+
+        let mut cmd = self.backend.build_command(&spec);
+        cmd.stdout(Stdio::piped());
+        cmd.stderr(Stdio::piped());
+
+        let mut child = cmd.spawn().map_err(|e| self.backend.spawn_error(e))?;
+
+        let stdout = child.stdout.take().expect("stdout was piped");
+        let stderr = child.stderr.take().expect("stderr was piped");
+        let handle = Arc::new(Mutex::new(child));
+        self.children.lock().unwrap_or_else(|_| panic!("{} children mutex poisoned", self.label)).insert(id, handle.clone());
+
+        let items = self.items.clone();
+        let active = self.active.clone();
+        let children = self.children.clone();
+        let manager = self.clone();
+        let backend = self.backend.clone();
+        let label = self.label;
+        // The backend's actual errors (and most warnings) usually land on
+        // stderr, not stdout; drain it on its own thread into a shared
+        // `logs` so (a) the child can't block once its stderr pipe buffer
+        // fills, and (b) a `Failed` item's log viewer shows the real error,
+        // not a stray progress line.
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let stderr_logs = logs.clone();
+        let stderr_thread = std::thread::spawn(move || {
+            let reader = BufReader::new(stderr);
+            for line in reader.lines().map_while(std::io::Result::ok) {
+                stderr_logs.lock().unwrap_or_else(|_| panic!("{label} logs mutex poisoned")).push(line);
+            }
+        });
+        std::thread::spawn(move || {
+            let reader = BufReader::new(stdout);
+            for line in reader.lines().map_while(std::io::Result::ok) {
+                logs.lock().unwrap_or_else(|_| panic!("{label} logs mutex poisoned")).push(line.clone());
+                if let Some((percent, speed, eta)) = backend.parse_progress(&line) {
+                    let mut items = items.lock().unwrap_or_else(|_| panic!("{label} items mutex poisoned"));
+                    if let Some(item) = items.get_mut(&id) {
+                        if let DownloadState::Downloading { percent: current, .. } = &item.state {
+                            let clamped = clamp_segment_progress(*current, percent);
+                            item.state = DownloadState::Downloading {
+                                started_at: match item.state {
+                                    DownloadState::Downloading { started_at, .. } => started_at,
+                                    _ => SystemTime::now(),
+                                },
+                                percent: clamped,
+                                speed: speed.clone(),
+                                eta: eta.clone(),
+                            };
+                        }
+                    }
+                }
+            }
+
+            // Poll with `try_wait` under short-lived locks rather than
+            // holding the mutex across a blocking `wait()` — the latter
+            // would starve `cancel`'s `kill()`, which needs the same lock,
+            // until the process exits on its own.
+            let status = loop {
+                match handle.lock().unwrap_or_else(|_| panic!("{label} child mutex poisoned")).try_wait() {
+                    Ok(Some(status)) => break Ok(status),
+                    Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+                    Err(e) => break Err(e),
+                }
+            };
+            let _ = stderr_thread.join();
+            let logs = logs.lock().unwrap_or_else(|_| panic!("{label} logs mutex poisoned")).clone();
+            children.lock().unwrap_or_else(|_| panic!("{label} children mutex poisoned")).remove(&id);
+            {
+                let mut items = items.lock().unwrap_or_else(|_| panic!("{label} items mutex poisoned"));
+                if let Some(item) = items.get_mut(&id) {
+                    // `cancel` may have already set this to `Canceled` and
+                    // killed the process; don't clobber that with the exit
+                    // status the kill produced.
+                    if !matches!(item.state, DownloadState::Canceled) {
+                        item.state = match status {
+                            Ok(status) if status.success() => DownloadState::Completed { path: String::new() },
+                            _ => DownloadState::Failed { logs },
+                        };
+                    }
+                }
+            }
+            *active.lock().unwrap_or_else(|_| panic!("{label} active mutex poisoned")) -= 1;
+            manager.try_schedule();
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_segment_progress_holds_non_decreasing_within_a_segment() {
+        assert_eq!(clamp_segment_progress(10.0, 5.0), 10.0);
+        assert_eq!(clamp_segment_progress(10.0, 20.0), 20.0);
+    }
+
+    #[test]
+    fn clamp_segment_progress_allows_reset_on_new_segment() {
+        // Video stream finished near 100%, then yt-dlp starts the audio
+        // stream from 0 — that reset should be let through, not clamped
+        // back up to the previous segment's percentage.
+        assert_eq!(clamp_segment_progress(95.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn clamp_segment_progress_ignores_low_readings_mid_segment() {
+        // A low reading that doesn't follow a near-complete percentage isn't
+        // a new segment — it's noise, so the display shouldn't regress.
+        assert_eq!(clamp_segment_progress(50.0, 0.5), 50.0);
+    }
+}
@@ -0,0 +1,148 @@
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::process_manager::{ProcessBackend, ProcessManager};
+
+pub use super::process_manager::{DownloadItem, DownloadMeta, DownloadState, DEFAULT_MAX_CONCURRENT};
+
+/// Returns true if `url` points at Spotify, so `DownloaderPane` can route it
+/// to [`SpotDlManager`] instead of `YtDlpManager`. Checks the parsed host
+/// rather than substring-matching the raw string, so a yt-dlp URL that
+/// merely mentions "open.spotify.com" in a query param or path segment
+/// isn't misrouted.
+pub fn is_spotify_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_owned))
+        .is_some_and(|host| host == "open.spotify.com" || host.ends_with(".open.spotify.com"))
+}
+
+#[derive(Clone)]
+struct PendingSpec {
+    url: String,
+}
+
+/// Plugs spotdl into [`ProcessManager`]'s queue/concurrency machinery.
+/// Unlike yt-dlp, spotdl has no line-oriented progress format we parse, so
+/// items stay at `Downloading` with 0% until they end as
+/// `Completed`/`Failed`, without intermediate percent updates.
+struct SpotDlBackend {
+    exe_path: String,
+}
+
+impl ProcessBackend<PendingSpec> for SpotDlBackend {
+    fn build_command(&self, spec: &PendingSpec) -> Command {
+        let mut cmd = Command::new(&self.exe_path);
+        cmd.args(["download", &spec.url]);
+        cmd
+    }
+
+    fn spawn_error(&self, error: std::io::Error) -> anyhow::Error {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            anyhow::anyhow!(
+                "spotdl executable not found at '{}' — install spotdl or configure its path",
+                self.exe_path
+            )
+        } else {
+            anyhow::Error::from(error).context(format!("failed to spawn spotdl at {}", self.exe_path))
+        }
+    }
+}
+
+/// Drives spotdl subprocesses for Spotify track/album/playlist URLs. Thin
+/// spotdl-specific wrapper around [`ProcessManager`]; see that type for the
+/// shared queue/cancel/requeue/concurrency machinery.
+///
+/// Shares [`ProcessManager`]'s `DownloadItem`/`DownloadState` machine with
+/// `YtDlpManager`, so `DownloaderPane` renders both backends' items
+/// identically.
+#[derive(Clone)]
+pub struct SpotDlManager {
+    inner: ProcessManager<PendingSpec>,
+}
+
+impl SpotDlManager {
+    pub fn new(exe_path: impl Into<String>) -> Self {
+        let backend = std::sync::Arc::new(SpotDlBackend { exe_path: exe_path.into() });
+        Self {
+            inner: ProcessManager::new("spotdl", backend as std::sync::Arc<dyn ProcessBackend<PendingSpec>>),
+        }
+    }
+
+    /// Current meta/state for one item, for the log viewer.
+    pub fn get(&self, id: Uuid) -> Option<DownloadItem> {
+        self.inner.get(id)
+    }
+
+    /// Cancels a `Queued` or `Downloading` item: kills its child process (if
+    /// already spawned) or drops it from the queue, then marks it `Canceled`.
+    pub fn cancel(&self, id: Uuid) -> Result<()> {
+        self.inner.cancel(id)
+    }
+
+    /// Requeues a `Failed` or `Canceled` item using its originally remembered URL.
+    pub fn requeue(&self, id: Uuid) -> Result<()> {
+        self.inner.requeue(id)
+    }
+
+    /// Snapshot of every tracked item alongside its id, in insertion order,
+    /// so a UI list can map a selected row back to the item to
+    /// `cancel`/`requeue`/`get` it.
+    pub fn snapshot(&self) -> Vec<(Uuid, DownloadItem)> {
+        self.inner.snapshot()
+    }
+
+    /// Number of processes currently running and still waiting in the queue.
+    pub fn counts(&self) -> (usize, usize) {
+        self.inner.counts()
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.inner.max_concurrent()
+    }
+
+    /// Adjusts the concurrency cap at runtime and immediately tries to
+    /// promote more queued items if it was raised.
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.inner.set_max_concurrent(max_concurrent);
+    }
+
+    /// Enqueues `url` as `Queued`; it's promoted to `Downloading` once a
+    /// concurrency permit is free.
+    pub fn download_url(&self, url: &str) -> Result<Uuid> {
+        let id = self.inner.enqueue(
+            PendingSpec { url: url.to_string() },
+            DownloadMeta {
+                id: url.to_string(),
+                kind: "spotify".to_string(),
+                ..Default::default()
+            },
+        );
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_spotify_url_matches_the_open_spotify_host() {
+        assert!(is_spotify_url("https://open.spotify.com/track/abc123"));
+        assert!(is_spotify_url("http://open.spotify.com/playlist/xyz?si=1"));
+    }
+
+    #[test]
+    fn is_spotify_url_rejects_hosts_that_merely_contain_the_string() {
+        assert!(!is_spotify_url("https://example.com/?redirect=open.spotify.com"));
+        assert!(!is_spotify_url("https://not-open.spotify.com.evil.test/track/abc"));
+        assert!(!is_spotify_url("https://youtube.com/watch?v=abc123"));
+    }
+
+    #[test]
+    fn is_spotify_url_rejects_unparseable_input() {
+        assert!(!is_spotify_url("not a url"));
+    }
+}
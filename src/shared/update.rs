@@ -0,0 +1,216 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+
+/// How long a bootstrapped yt-dlp binary is trusted before the app should
+/// prompt to re-check for a newer release.
+pub const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24 * 14);
+
+const LATEST_RELEASE_BASE: &str = "https://github.com/yt-dlp/yt-dlp/releases/latest/download";
+
+/// Directory TerminalMan stores binaries it bootstraps on the user's behalf.
+fn data_dir() -> Result<PathBuf> {
+    let base = dirs::data_dir().context("could not determine platform data directory")?;
+    let dir = base.join("terminalman").join("bin");
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    Ok(dir)
+}
+
+/// Path yt-dlp is bootstrapped to, independent of whether it's been fetched yet.
+pub fn bootstrapped_yt_dlp_path() -> Result<PathBuf> {
+    let name = if cfg!(windows) { "yt-dlp.exe" } else { "yt-dlp" };
+    Ok(data_dir()?.join(name))
+}
+
+/// GitHub release asset name for the running OS/arch.
+///
+/// Matches the asset names yt-dlp publishes at
+/// <https://github.com/yt-dlp/yt-dlp/releases>.
+fn release_asset_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "yt-dlp.exe"
+    } else if cfg!(target_os = "macos") {
+        "yt-dlp_macos"
+    } else if cfg!(target_arch = "aarch64") {
+        "yt-dlp_linux_aarch64"
+    } else {
+        "yt-dlp_linux"
+    }
+}
+
+/// Name of the checksum manifest yt-dlp publishes alongside its release
+/// binaries, one `<sha256>  <filename>` pair per line.
+const CHECKSUMS_ASSET: &str = "SHA2-256SUMS";
+
+/// Fetches yt-dlp's published `SHA2-256SUMS` and returns the expected
+/// lowercase hex digest for `asset_name`, so [`fetch_latest_yt_dlp`] can
+/// verify the binary it just downloaded before installing it.
+fn expected_checksum(asset_name: &str) -> Result<String> {
+    let url = format!("{LATEST_RELEASE_BASE}/{CHECKSUMS_ASSET}");
+    let body = ureq::get(&url)
+        .call()
+        .with_context(|| format!("failed to fetch {url}"))?
+        .into_string()
+        .context("failed to read yt-dlp checksums file")?;
+    parse_checksum(&body, asset_name)
+        .with_context(|| format!("no {CHECKSUMS_ASSET} entry for {asset_name}"))
+}
+
+/// Parses a `<sha256>  <filename>` manifest body, returning the lowercase
+/// hex digest for the line naming `asset_name` (`*`-prefixed, binary-mode
+/// filenames are matched too).
+fn parse_checksum(body: &str, asset_name: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == asset_name).then(|| hash.to_lowercase())
+    })
+}
+
+/// Downloads the platform-appropriate yt-dlp binary from the latest GitHub
+/// release into the crate data dir and marks it executable, reporting
+/// progress as bytes arrive so the caller can drive a [`ratatui::widgets::Gauge`].
+///
+/// Verifies the download against yt-dlp's published `SHA2-256SUMS` before
+/// installing it — a compromised CDN edge, a MITM'd connection, or a
+/// truncated transfer would otherwise leave an unverified binary wired up
+/// as the executable every future download runs.
+///
+/// [`ratatui::widgets::Gauge`]: https://docs.rs/ratatui/latest/ratatui/widgets/struct.Gauge.html
+pub fn fetch_latest_yt_dlp(mut on_progress: impl FnMut(f32)) -> Result<PathBuf> {
+    let asset_name = release_asset_name();
+    let url = format!("{LATEST_RELEASE_BASE}/{asset_name}");
+    let path = bootstrapped_yt_dlp_path()?;
+
+    let expected_checksum = expected_checksum(asset_name)?;
+
+    let response = ureq::get(&url).call().with_context(|| format!("failed to fetch {url}"))?;
+    let total_len: Option<u64> = response
+        .header("Content-Length")
+        .and_then(|len| len.parse().ok());
+
+    let mut reader = response.into_reader();
+    let mut bytes = Vec::new();
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut chunk).context("failed to read yt-dlp download")?;
+        if read == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..read]);
+        if let Some(total) = total_len {
+            on_progress((bytes.len() as f32 / total as f32 * 100.0).clamp(0.0, 100.0));
+        }
+    }
+    on_progress(100.0);
+
+    // `reader.read` returning `Ok(0)` is also what a connection dropped
+    // mid-transfer looks like; don't take it as a clean EOF when the server
+    // told us up front how many bytes to expect and we got fewer.
+    if let Some(total) = total_len {
+        anyhow::ensure!(
+            bytes.len() as u64 == total,
+            "yt-dlp download was truncated: got {} of {total} bytes",
+            bytes.len()
+        );
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(&bytes);
+    let actual_checksum = hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    anyhow::ensure!(
+        actual_checksum == expected_checksum,
+        "yt-dlp download failed checksum verification (expected {expected_checksum}, got {actual_checksum}) — refusing to install a possibly corrupted or tampered binary"
+    );
+
+    let tmp_path = path.with_extension("tmp");
+    fs::File::create(&tmp_path)
+        .and_then(|mut f| f.write_all(&bytes))
+        .with_context(|| format!("failed to write {}", tmp_path.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp_path, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("failed to mark {} executable", tmp_path.display()))?;
+    }
+
+    fs::rename(&tmp_path, &path).with_context(|| format!("failed to install {}", path.display()))?;
+    Ok(path)
+}
+
+/// True if `path` doesn't exist, or exists but was last bootstrapped more
+/// than `max_age` ago — the startup prompt to re-check for an update.
+pub fn needs_update(path: &Path, max_age: Duration) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return true;
+    };
+    let Ok(modified) = metadata.modified() else {
+        return true;
+    };
+    SystemTime::now()
+        .duration_since(modified)
+        .map(|age| age > max_age)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_checksum_finds_the_matching_asset_line() {
+        let body = "abc123  yt-dlp_linux\ndef456  yt-dlp_macos\n";
+        assert_eq!(parse_checksum(body, "yt-dlp_macos").as_deref(), Some("def456"));
+    }
+
+    #[test]
+    fn parse_checksum_strips_the_binary_mode_marker_and_lowercases() {
+        let body = "ABC123 *yt-dlp.exe\n";
+        assert_eq!(parse_checksum(body, "yt-dlp.exe").as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn parse_checksum_returns_none_for_an_unlisted_asset() {
+        assert_eq!(parse_checksum("abc123  yt-dlp_linux\n", "yt-dlp_macos"), None);
+    }
+
+    #[test]
+    fn release_asset_name_returns_a_known_yt_dlp_asset() {
+        let name = release_asset_name();
+        assert!(["yt-dlp.exe", "yt-dlp_macos", "yt-dlp_linux_aarch64", "yt-dlp_linux"]
+            .contains(&name));
+    }
+
+    #[test]
+    fn needs_update_is_true_when_the_path_does_not_exist() {
+        let path = std::env::temp_dir().join(format!("terminalman-test-missing-{}", std::process::id()));
+        assert!(needs_update(&path, Duration::from_secs(3600)));
+    }
+
+    #[test]
+    fn needs_update_is_false_for_a_freshly_written_file() {
+        let path = std::env::temp_dir().join(format!("terminalman-test-fresh-{}", std::process::id()));
+        fs::write(&path, b"binary").unwrap();
+        assert!(!needs_update(&path, Duration::from_secs(3600)));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn needs_update_is_true_once_the_file_is_older_than_max_age() {
+        let path = std::env::temp_dir().join(format!("terminalman-test-stale-{}", std::process::id()));
+        fs::write(&path, b"binary").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(needs_update(&path, Duration::from_millis(1)));
+        let _ = fs::remove_file(&path);
+    }
+}
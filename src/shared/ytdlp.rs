@@ -0,0 +1,529 @@
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::process_manager::{ProcessBackend, ProcessManager};
+use super::update;
+
+pub use super::process_manager::{DownloadItem, DownloadMeta, DownloadState, DEFAULT_MAX_CONCURRENT};
+
+/// `DownloadMeta::kind` used for the pseudo-item tracking a yt-dlp self-update.
+const UPDATE_KIND: &str = "update";
+
+/// Shape of `yt-dlp -J --flat-playlist <url>` output, trimmed to the fields
+/// the pane renders. A playlist URL comes back with `entries` populated and
+/// most top-level fields empty; a single video has no `entries`.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct YtDlpInfo {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    uploader: Option<String>,
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    thumbnail: Option<String>,
+    #[serde(default)]
+    webpage_url: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    playlist_count: Option<usize>,
+    #[serde(default)]
+    entries: Vec<YtDlpInfo>,
+}
+
+impl YtDlpInfo {
+    fn into_meta(self, playlist_count: Option<usize>) -> (String, DownloadMeta) {
+        let resolved_url = self
+            .webpage_url
+            .or(self.url)
+            .unwrap_or_else(|| self.id.clone());
+        let meta = DownloadMeta {
+            id: resolved_url.clone(),
+            kind: "video".to_string(),
+            title: self.title,
+            uploader: self.uploader,
+            duration: self.duration,
+            thumbnail: self.thumbnail,
+            playlist_count,
+        };
+        (resolved_url, meta)
+    }
+}
+
+/// Runs `yt-dlp -J --flat-playlist <url>` and parses the resulting info dict.
+fn fetch_info(exe_path: &str, url: &str) -> Result<YtDlpInfo> {
+    let output = Command::new(exe_path)
+        .args(["-J", "--flat-playlist", url])
+        .output()
+        .with_context(|| format!("failed to run yt-dlp at {exe_path} for metadata"))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "yt-dlp -J exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    serde_json::from_slice(&output.stdout).context("failed to parse yt-dlp JSON info dict")
+}
+
+fn progress_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // `_speed_str`/`_eta_str` are each normally a single whitespace-free
+        // token ("1.23MiB/s", "00:07"), but yt-dlp renders them as the
+        // two-word "Unknown speed"/"Unknown ETA" whenever it doesn't know
+        // the value yet — which is the common case for the first second or
+        // so of every download. Match those literally (ahead of the
+        // single-token fallback) so they come through as the `Unknown ...`
+        // placeholder instead of splitting across the speed/eta groups.
+        Regex::new(r"([\d.]+)%\s+(Unknown speed|\S+)\s+(Unknown ETA|\S+)").expect("static regex is valid")
+    })
+}
+
+/// Parses one line of yt-dlp's `--newline --progress-template` output.
+///
+/// Returns `None` for lines that carry no percent (e.g. `[Merger]` lines),
+/// so callers can leave the last known progress intact instead of resetting it.
+fn parse_progress_line(line: &str) -> Option<(f32, String, String)> {
+    let caps = progress_regex().captures(line)?;
+    let percent: f32 = caps.get(1)?.as_str().parse().ok()?;
+    let speed = caps.get(2)?.as_str().to_string();
+    let eta = caps.get(3)?.as_str().to_string();
+    Some((percent, speed, eta))
+}
+
+#[derive(Clone)]
+struct PendingSpec {
+    url: String,
+    format_args: Vec<String>,
+}
+
+/// Plugs yt-dlp into [`ProcessManager`]'s queue/concurrency machinery:
+/// builds the download `Command`, parses its progress lines, and — as the
+/// [`ProcessBackend::prepare`] hook — runs the `yt-dlp -J` metadata fetch
+/// (and any playlist expansion) under the same reserved concurrency permit
+/// as the download itself, auto-bootstrapping a binary first if none works.
+struct YtDlpBackend {
+    exe_path: Arc<Mutex<String>>,
+    /// Id of the auto-bootstrap fetch [`Self::ensure_bootstrapping`] is
+    /// currently waiting on, if any. A batch of pasted URLs prepares
+    /// concurrently on one thread each, and without this guard every one of
+    /// them would see no working binary and kick off its own
+    /// `update_yt_dlp` fetch, all writing the same bootstrapped-binary tmp
+    /// path at once.
+    bootstrap_inflight: Arc<Mutex<Option<Uuid>>>,
+}
+
+impl YtDlpBackend {
+    /// Path yt-dlp is currently invoked at — either the configured path or,
+    /// after a self-update, the bootstrapped binary in the crate data dir.
+    fn exe_path(&self) -> String {
+        self.exe_path.lock().expect("ytdlp exe_path mutex poisoned").clone()
+    }
+
+    /// True if the binary at the currently configured path (or on `PATH`)
+    /// can actually be run — used to decide whether to auto-bootstrap.
+    fn binary_available(&self) -> bool {
+        Command::new(self.exe_path())
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok()
+    }
+
+    /// Fetches the latest yt-dlp release for this platform as a pseudo
+    /// download item (so `DownloaderPane` can render it with the same
+    /// progress gauge), then switches `exe_path` to the bootstrapped binary.
+    /// Returns the pseudo-item's id either way.
+    fn spawn_update_fetch(&self, manager: &ProcessManager<PendingSpec>) -> Uuid {
+        let id = manager.insert_untracked(DownloadItem {
+            inner: DownloadMeta {
+                id: "yt-dlp".to_string(),
+                kind: UPDATE_KIND.to_string(),
+                title: Some("Fetching latest yt-dlp release".to_string()),
+                ..Default::default()
+            },
+            state: DownloadState::Downloading {
+                started_at: std::time::SystemTime::now(),
+                percent: 0.0,
+                speed: String::new(),
+                eta: String::new(),
+            },
+        });
+
+        let manager = manager.clone();
+        let exe_path = self.exe_path.clone();
+        let bootstrap_inflight = self.bootstrap_inflight.clone();
+        std::thread::spawn(move || {
+            let progress_manager = manager.clone();
+            let result = update::fetch_latest_yt_dlp(move |percent| {
+                progress_manager.update_progress(id, percent);
+            });
+
+            // Release the auto-bootstrap guard regardless of outcome, so a
+            // later download can retry the fetch if this one failed (or was
+            // canceled) instead of waiting on a fetch that's already done.
+            {
+                let mut inflight = bootstrap_inflight
+                    .lock()
+                    .expect("ytdlp bootstrap_inflight mutex poisoned");
+                if *inflight == Some(id) {
+                    *inflight = None;
+                }
+            }
+
+            // `cancel` may have already marked this `Canceled`; don't let a
+            // fetch that was already in flight revert that a moment later.
+            let canceled = matches!(
+                manager.get(id).map(|item| item.state),
+                Some(DownloadState::Canceled)
+            );
+            if canceled {
+                return;
+            }
+
+            match result {
+                Ok(path) => {
+                    *exe_path.lock().expect("ytdlp exe_path mutex poisoned") =
+                        path.to_string_lossy().into_owned();
+                    manager.set_state(
+                        id,
+                        DownloadState::Completed {
+                            path: path.to_string_lossy().into_owned(),
+                        },
+                    );
+                }
+                Err(e) => {
+                    manager.set_state(id, DownloadState::Failed { logs: vec![e.to_string()] });
+                }
+            }
+        });
+
+        id
+    }
+
+    /// Starts the auto-bootstrap fetch if none is already running, returning
+    /// its item id either way.
+    ///
+    /// `binary_available`/`spawn_update_fetch` on their own are a
+    /// check-and-act race: several prepared items (or a prepared item and a
+    /// manual "update yt-dlp" keypress) can reach this around the same
+    /// time, so without this guard each would see no working binary and
+    /// start its own fetch, all overwriting the same bootstrapped-binary tmp
+    /// path. Gating the check-and-start under `bootstrap_inflight`'s single
+    /// lock means only the first caller starts a fetch; the rest just wait
+    /// on (or, for a manual trigger, just watch) the id it started.
+    fn ensure_bootstrapping(&self, manager: &ProcessManager<PendingSpec>) -> Uuid {
+        let mut inflight = self
+            .bootstrap_inflight
+            .lock()
+            .expect("ytdlp bootstrap_inflight mutex poisoned");
+        if let Some(id) = *inflight {
+            return id;
+        }
+        let id = self.spawn_update_fetch(manager);
+        *inflight = Some(id);
+        id
+    }
+}
+
+impl ProcessBackend<PendingSpec> for YtDlpBackend {
+    fn build_command(&self, spec: &PendingSpec) -> Command {
+        let mut cmd = Command::new(self.exe_path());
+        cmd.args([
+            "--newline",
+            "--progress-template",
+            "%(progress._percent_str)s %(progress._speed_str)s %(progress._eta_str)s",
+        ]);
+        cmd.args(&spec.format_args);
+        cmd.arg(&spec.url);
+        cmd
+    }
+
+    fn parse_progress(&self, line: &str) -> Option<(f32, String, String)> {
+        parse_progress_line(line)
+    }
+
+    fn spawn_error(&self, error: std::io::Error) -> anyhow::Error {
+        anyhow::Error::from(error).context(format!("failed to spawn yt-dlp at {}", self.exe_path()))
+    }
+
+    /// Fetches metadata for `id`'s url and patches it into the already
+    /// `Queued` item in place; for a playlist, enqueues one additional item
+    /// per extra entry and returns the spec for the first entry instead.
+    ///
+    /// Runs under the concurrency permit `try_schedule` reserved for `id`,
+    /// so this blocking fetch is bounded by the same `max_concurrent` limit
+    /// as the download itself, and doesn't block the UI thread either.
+    ///
+    /// If no working yt-dlp binary is found yet, this bootstraps one first
+    /// (blocking this background thread, not the UI) instead of letting the
+    /// metadata fetch fail outright.
+    fn prepare(&self, manager: &ProcessManager<PendingSpec>, id: Uuid, spec: PendingSpec) -> PendingSpec {
+        if !self.binary_available() {
+            let update_id = self.ensure_bootstrapping(manager);
+            loop {
+                let state = manager.get(update_id).map(|item| item.state);
+                if !matches!(state, Some(DownloadState::Downloading { .. })) {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+        }
+
+        match fetch_info(&self.exe_path(), &spec.url) {
+            Ok(info) if !info.entries.is_empty() => {
+                let playlist_count = info.playlist_count.or(Some(info.entries.len()));
+                let mut entries = info.entries.into_iter();
+                let first = entries.next().expect("checked non-empty above");
+                let (first_url, first_meta) = first.into_meta(playlist_count);
+                manager.patch_meta(id, first_meta);
+                for entry in entries {
+                    let (entry_url, meta) = entry.into_meta(playlist_count);
+                    manager.enqueue(
+                        PendingSpec {
+                            url: entry_url,
+                            format_args: spec.format_args.clone(),
+                        },
+                        meta,
+                    );
+                }
+                PendingSpec {
+                    url: first_url,
+                    format_args: spec.format_args,
+                }
+            }
+            Ok(info) => {
+                manager.patch_meta(
+                    id,
+                    DownloadMeta {
+                        id: spec.url.clone(),
+                        kind: "video".to_string(),
+                        title: info.title,
+                        uploader: info.uploader,
+                        duration: info.duration,
+                        thumbnail: info.thumbnail,
+                        playlist_count: None,
+                    },
+                );
+                spec
+            }
+            Err(_) => spec,
+        }
+    }
+}
+
+/// Drives yt-dlp subprocesses and tracks their [`DownloadItem`] state. Thin
+/// yt-dlp-specific wrapper around [`ProcessManager`]; see that type for the
+/// shared queue/cancel/requeue/concurrency machinery.
+#[derive(Clone)]
+pub struct YtDlpManager {
+    inner: ProcessManager<PendingSpec>,
+    backend: Arc<YtDlpBackend>,
+}
+
+impl YtDlpManager {
+    pub fn new(exe_path: impl Into<String>) -> Self {
+        let backend = Arc::new(YtDlpBackend {
+            exe_path: Arc::new(Mutex::new(exe_path.into())),
+            bootstrap_inflight: Arc::new(Mutex::new(None)),
+        });
+        Self {
+            inner: ProcessManager::new("ytdlp", backend.clone() as Arc<dyn ProcessBackend<PendingSpec>>),
+            backend,
+        }
+    }
+
+    /// Current meta/state for one item, for the log viewer.
+    pub fn get(&self, id: Uuid) -> Option<DownloadItem> {
+        self.inner.get(id)
+    }
+
+    /// Cancels a `Queued` or `Downloading` item: kills its child process (if
+    /// already spawned) or drops it from the queue, then marks it `Canceled`.
+    pub fn cancel(&self, id: Uuid) -> Result<()> {
+        self.inner.cancel(id)
+    }
+
+    /// Requeues a `Failed` or `Canceled` item using its originally remembered
+    /// URL/format.
+    pub fn requeue(&self, id: Uuid) -> Result<()> {
+        self.inner.requeue(id)
+    }
+
+    /// Path yt-dlp is currently invoked at — either the configured path or,
+    /// after a self-update, the bootstrapped binary in the crate data dir.
+    pub fn exe_path(&self) -> String {
+        self.backend.exe_path()
+    }
+
+    /// True if the configured binary doesn't actually run, or — when it's
+    /// the one this app bootstrapped itself — is older than `max_age`. The
+    /// startup-time "prompt to update" check.
+    ///
+    /// A system-installed yt-dlp the user configured themselves is never
+    /// flagged as stale just because it's old; staleness only applies to
+    /// the bootstrapped binary, which this app is responsible for keeping
+    /// fresh.
+    pub fn update_recommended(&self, max_age: std::time::Duration) -> bool {
+        let exe_path = self.backend.exe_path();
+        match update::bootstrapped_yt_dlp_path() {
+            Ok(bootstrapped) if bootstrapped.to_string_lossy() == exe_path => {
+                update::needs_update(&bootstrapped, max_age)
+            }
+            _ => !self.backend.binary_available(),
+        }
+    }
+
+    /// Fetches the latest yt-dlp release for this platform as a pseudo
+    /// download item (so `DownloaderPane` can render it with the same
+    /// progress gauge), then switches `exe_path` to the bootstrapped binary.
+    /// Bound to the "update yt-dlp" keybinding; [`YtDlpBackend::prepare`]
+    /// starts the same fetch the first time a download is requested and no
+    /// working binary is found.
+    ///
+    /// Goes through [`YtDlpBackend::ensure_bootstrapping`] rather than
+    /// calling `spawn_update_fetch` directly, so pressing the keybinding
+    /// while a download's `prepare` has already kicked off an auto-bootstrap
+    /// fetch joins that fetch instead of racing it with a second one that
+    /// writes the same bootstrapped-binary tmp path.
+    pub fn update_yt_dlp(&self) -> Uuid {
+        self.backend.ensure_bootstrapping(&self.inner)
+    }
+
+    /// Snapshot of every tracked item alongside its id, in insertion order,
+    /// so a UI list can map a selected row back to the item to
+    /// `cancel`/`requeue`/`get` it.
+    pub fn snapshot(&self) -> Vec<(Uuid, DownloadItem)> {
+        self.inner.snapshot()
+    }
+
+    /// Number of processes currently running and still waiting in the queue,
+    /// for the "N active / M queued" status line.
+    pub fn counts(&self) -> (usize, usize) {
+        self.inner.counts()
+    }
+
+    pub fn max_concurrent(&self) -> usize {
+        self.inner.max_concurrent()
+    }
+
+    /// Adjusts the concurrency cap at runtime and immediately tries to
+    /// promote more queued items if it was raised.
+    pub fn set_max_concurrent(&self, max_concurrent: usize) {
+        self.inner.set_max_concurrent(max_concurrent);
+    }
+
+    /// Enqueues `url` as a bare `Queued` item synchronously — it shows up in
+    /// the list immediately — and lets [`ProcessManager::try_schedule`]
+    /// promote it once a concurrency permit is free.
+    ///
+    /// `format_args` are extra yt-dlp flags (e.g. from a [`FormatPreset`])
+    /// inserted ahead of the URL, such as `-x --audio-format m4a`. The
+    /// metadata fetch (`yt-dlp -J --flat-playlist`) and any playlist
+    /// expansion happen later, in [`YtDlpBackend::prepare`], inside the
+    /// same scheduled slot as the download itself — so a long paste list
+    /// can't fork more concurrent `yt-dlp -J` processes than `max_concurrent`
+    /// allows, the same bound that already governs the actual downloads.
+    ///
+    /// [`FormatPreset`]: crate::shared::format::FormatPreset
+    pub fn download_url(&self, url: &str, format_args: &[String]) -> Result<()> {
+        self.inner.enqueue(
+            PendingSpec {
+                url: url.to_string(),
+                format_args: format_args.to_vec(),
+            },
+            DownloadMeta {
+                id: url.to_string(),
+                kind: "video".to_string(),
+                ..Default::default()
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_progress_line_reads_percent_speed_eta() {
+        let (percent, speed, eta) = parse_progress_line("  42.5% 1.23MiB/s 00:07").unwrap();
+        assert_eq!(percent, 42.5);
+        assert_eq!(speed, "1.23MiB/s");
+        assert_eq!(eta, "00:07");
+    }
+
+    #[test]
+    fn parse_progress_line_ignores_lines_without_a_percent() {
+        assert!(parse_progress_line("[Merger] Merging formats into \"out.mp4\"").is_none());
+        assert!(parse_progress_line("").is_none());
+    }
+
+    #[test]
+    fn parse_progress_line_handles_unknown_speed_and_eta_placeholders() {
+        let (percent, speed, eta) = parse_progress_line("0.0% Unknown speed Unknown ETA").unwrap();
+        assert_eq!(percent, 0.0);
+        assert_eq!(speed, "Unknown speed");
+        assert_eq!(eta, "Unknown ETA");
+    }
+
+    #[test]
+    fn parse_progress_line_handles_a_mix_of_known_and_unknown_fields() {
+        let (_, speed, eta) = parse_progress_line("12.0% 1.2MiB/s Unknown ETA").unwrap();
+        assert_eq!(speed, "1.2MiB/s");
+        assert_eq!(eta, "Unknown ETA");
+
+        let (_, speed, eta) = parse_progress_line("12.0% Unknown speed 00:07").unwrap();
+        assert_eq!(speed, "Unknown speed");
+        assert_eq!(eta, "00:07");
+    }
+
+    #[test]
+    fn into_meta_prefers_webpage_url_over_url_and_id() {
+        let info = YtDlpInfo {
+            id: "abc123".to_string(),
+            webpage_url: Some("https://example.com/watch?v=abc123".to_string()),
+            url: Some("https://example.com/raw".to_string()),
+            ..Default::default()
+        };
+        let (resolved_url, meta) = info.into_meta(None);
+        assert_eq!(resolved_url, "https://example.com/watch?v=abc123");
+        assert_eq!(meta.id, "https://example.com/watch?v=abc123");
+    }
+
+    #[test]
+    fn into_meta_falls_back_to_url_then_id() {
+        let info = YtDlpInfo {
+            id: "abc123".to_string(),
+            url: Some("https://example.com/raw".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(info.into_meta(None).0, "https://example.com/raw");
+
+        let info = YtDlpInfo {
+            id: "abc123".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(info.into_meta(None).0, "abc123");
+    }
+
+    #[test]
+    fn into_meta_carries_the_caller_supplied_playlist_count() {
+        let info = YtDlpInfo::default();
+        let (_, meta) = info.into_meta(Some(12));
+        assert_eq!(meta.playlist_count, Some(12));
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifies a single-line input buffer owned by a pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BufferId(u64);
+
+impl BufferId {
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Outcome of feeding a key through a buffer while it's in insert mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputResultEvent {
+    Confirm,
+    Cancel,
+    Changed,
+}
+
+#[derive(Default)]
+struct Buffer {
+    value: String,
+}
+
+/// Tracks every pane's input buffers plus which one (if any) is in insert mode.
+#[derive(Default)]
+pub struct InputManager {
+    buffers: HashMap<BufferId, Buffer>,
+    active: Option<BufferId>,
+}
+
+impl InputManager {
+    pub fn create_buffer(&mut self, id: BufferId, initial: Option<&str>) {
+        self.buffers.insert(
+            id,
+            Buffer {
+                value: initial.unwrap_or_default().to_string(),
+            },
+        );
+    }
+
+    pub fn value(&self, id: BufferId) -> &str {
+        self.buffers.get(&id).map(|b| b.value.as_str()).unwrap_or("")
+    }
+
+    pub fn is_active(&self, id: BufferId) -> bool {
+        self.active == Some(id)
+    }
+
+    pub fn insert_mode(&mut self, id: BufferId) {
+        self.active = Some(id);
+    }
+
+    pub fn normal_mode(&mut self) {
+        self.active = None;
+    }
+
+    pub fn clear_buffer(&mut self, id: BufferId) {
+        if let Some(buf) = self.buffers.get_mut(&id) {
+            buf.value.clear();
+        }
+    }
+}
@@ -1,33 +1,146 @@
 use anyhow::Result;
 use ratatui::{
-    layout::{Constraint, Layout, Rect},
-    style::Style,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, Gauge, Paragraph},
 };
+use uuid::Uuid;
 
 use super::Pane;
 use crate::{
     ctx::Ctx,
     shared::{
+        format::FormatPreset,
         keys::ActionEvent,
         macros::{status_error, status_info},
+        spotdl::is_spotify_url,
+        ytdlp::{DownloadItem, DownloadState},
     },
     ui::{
         input::{BufferId, InputResultEvent},
     },
 };
 
+/// Which manager a selected row's id belongs to, since `YtDlpManager` and
+/// `SpotDlManager` each mint their own `Uuid` keyspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    YtDlp,
+    SpotDl,
+}
+
+/// Scroll state for the full-log popup opened on a `Failed` item.
+#[derive(Debug)]
+struct LogView {
+    backend: Backend,
+    id: Uuid,
+    scroll: usize,
+}
+
+/// Snapshot of every row shown in "Recent Downloads", in render order:
+/// yt-dlp items followed by spotdl items. Rebuilt on every render and every
+/// action so selection always acts on current state.
+fn combined_entries(ctx: &Ctx) -> Vec<(Backend, Uuid, DownloadItem)> {
+    let mut entries: Vec<(Backend, Uuid, DownloadItem)> = ctx
+        .ytdlp_manager
+        .snapshot()
+        .into_iter()
+        .map(|(id, item)| (Backend::YtDlp, id, item))
+        .collect();
+    entries.extend(
+        ctx.spotdl_manager
+            .snapshot()
+            .into_iter()
+            .map(|(id, item)| (Backend::SpotDl, id, item)),
+    );
+    entries
+}
+
+/// Resolves a remembered `(Backend, Uuid)` selection to its current row
+/// index in `entries`. Since yt-dlp and spotdl rows are concatenated and
+/// either sublist can grow independently, a bare index would drift under
+/// the selected row whenever the *other* backend enqueues something ahead
+/// of it; tracking the id instead keeps selection pinned to the same row.
+fn resolve_selected(entries: &[(Backend, Uuid, DownloadItem)], selected: Option<(Backend, Uuid)>) -> usize {
+    selected
+        .and_then(|(backend, id)| entries.iter().position(|(b, i, _)| *b == backend && *i == id))
+        .unwrap_or(0)
+}
+
+/// Shrinks `area` to a centered rectangle `percent_x`/`percent_y` of its
+/// size, for the log-viewer popup.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+/// Human-readable label for a download row: `title by uploader (mm:ss)` when
+/// metadata arrived, falling back to the raw id/kind while it's still
+/// pending or if the metadata fetch failed. Playlist entries get a
+/// `[playlist of total]` prefix (the entry's own position is not shown).
+fn display_name(meta: &crate::shared::ytdlp::DownloadMeta) -> String {
+    let mut label = match &meta.title {
+        Some(title) => title.clone(),
+        None => format!("{} : {}", meta.id, meta.kind),
+    };
+    if let Some(uploader) = &meta.uploader {
+        label.push_str(&format!(" by {uploader}"));
+    }
+    if let Some(duration) = meta.duration {
+        let secs = duration as u64;
+        label.push_str(&format!(" ({}:{:02})", secs / 60, secs % 60));
+    }
+    if let Some(count) = meta.playlist_count {
+        label = format!("[playlist of {count}] {label}");
+    }
+    label
+}
+
 #[derive(Debug)]
 pub struct DownloaderPane {
     input_id: BufferId,
+    format_preset: FormatPreset,
+    /// Identifies the highlighted row by `(Backend, Uuid)` rather than a
+    /// bare index, so it can't drift when the *other* backend's sublist
+    /// grows or shrinks underneath it. Resolved to an index via
+    /// [`resolve_selected`] each time it's needed.
+    selected: Option<(Backend, Uuid)>,
+    /// Open when `Enter`/`l` is pressed on a `Failed` row, to show its full
+    /// captured `logs` instead of just the last non-empty line.
+    log_view: Option<LogView>,
 }
 
 impl DownloaderPane {
-    pub fn new(ctx: &Ctx) -> Self {
+    pub fn new(ctx: &mut Ctx) -> Self {
         let input_id = BufferId::new();
         ctx.input.create_buffer(input_id, None);
-        Self { input_id }
+        if ctx
+            .ytdlp_manager
+            .update_recommended(ctx.config.update_check_interval())
+        {
+            status_info!("yt-dlp binary is missing or stale — press 'u' to fetch the latest release");
+        }
+        Self {
+            input_id,
+            format_preset: ctx.config.last_format_preset(),
+            selected: None,
+            log_view: None,
+        }
     }
 }
 
@@ -48,9 +161,16 @@ impl Pane for DownloaderPane {
             Style::default()
         };
 
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title(" Paste YouTube/SoundCloud URL (Press 'i' to edit, 'Enter' to download) ");
+        let (yt_active, yt_queued) = ctx.ytdlp_manager.counts();
+        let (sp_active, sp_queued) = ctx.spotdl_manager.counts();
+        let block = Block::default().borders(Borders::ALL).title(format!(
+            " Paste YouTube/SoundCloud/Spotify URL ('i' edit, Enter download, 'f' format: {}, 'u' update yt-dlp) — {} active / {} queued (+/- cap: {}, [/] staleness: {}d) ",
+            self.format_preset.label(),
+            yt_active + sp_active,
+            yt_queued + sp_queued,
+            ctx.ytdlp_manager.max_concurrent(),
+            ctx.config.update_check_interval_days()
+        ));
         
         let mut text = value.to_string();
         if ctx.input.is_active(self.input_id) {
@@ -60,50 +180,135 @@ impl Pane for DownloaderPane {
         let paragraph = Paragraph::new(text).block(block).style(style);
         frame.render_widget(paragraph, input_area);
 
-        // Render Downloads List (from YtDlpManager)
-        let items: Vec<ListItem> = ctx.ytdlp_manager.map_values(|item| {
-            let (status, color) = match &item.state {
-                crate::shared::ytdlp::DownloadState::Queued => ("Queued".to_string(), ratatui::style::Color::Gray),
-                crate::shared::ytdlp::DownloadState::Downloading { started_at } => {
-                    let elapsed = started_at.elapsed().unwrap_or_default();
-                    let secs = elapsed.as_secs();
-                    let time_str = if secs < 60 {
-                        format!("{}s", secs)
-                    } else {
-                        format!("{}m {}s", secs / 60, secs % 60)
-                    };
-                    (format!("Downloading... ({})", time_str), ratatui::style::Color::Yellow)
+        // Render Downloads List (yt-dlp and spotdl items). Downloading items get a
+        // two-row slot (gauge + speed/ETA) instead of a single `List` line, so
+        // `List` can't host them directly; lay the rows out by hand instead.
+        let list_block = Block::default().borders(Borders::ALL).title(" Recent Downloads ");
+        let list_inner = list_block.inner(list_area);
+        frame.render_widget(list_block, list_area);
+
+        let entries = combined_entries(ctx);
+        let selected_idx = resolve_selected(&entries, self.selected);
+        if let Some((backend, id, _)) = entries.get(selected_idx) {
+            self.selected = Some((*backend, *id));
+        }
+
+        let row_heights: Vec<Constraint> = entries
+            .iter()
+            .map(|(_, _, item)| {
+                if matches!(item.state, DownloadState::Downloading { .. }) {
+                    Constraint::Length(2)
+                } else {
+                    Constraint::Length(1)
                 }
-                crate::shared::ytdlp::DownloadState::Completed { .. } => ("Completed".to_string(), ratatui::style::Color::Green),
-                crate::shared::ytdlp::DownloadState::AlreadyDownloaded { .. } => ("Already Downloaded".to_string(), ratatui::style::Color::Green),
-                crate::shared::ytdlp::DownloadState::Failed { logs } => {
-                    // Try to find a meaningful error message from the end of the logs
-                    let error = logs
-                        .iter()
-                        .rev()
-                        .find(|line| !line.trim().is_empty() && !line.contains("yt-dlp exited with code"))
-                        .cloned()
-                        .unwrap_or_else(|| "Unknown error".to_string());
-                    (format!("Failed: {}", error), ratatui::style::Color::Red)
+            })
+            .chain(std::iter::once(Constraint::Min(0)))
+            .collect();
+        let rows = Layout::default().constraints(row_heights).split(list_inner);
+
+        for (idx, ((_backend, _id, item), row)) in entries.iter().zip(rows.iter()).enumerate() {
+            let marker = if idx == selected_idx { "\u{25b6} " } else { "  " };
+            let display_name = format!("{marker}{}", display_name(&item.inner));
+            let row_style = if idx == selected_idx {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            match &item.state {
+                DownloadState::Downloading { percent, speed, eta, .. } => {
+                    let lines = Layout::default()
+                        .constraints([Constraint::Length(1), Constraint::Length(1)])
+                        .split(*row);
+                    let gauge = Gauge::default()
+                        .gauge_style(Style::default().fg(ratatui::style::Color::Yellow))
+                        .label(format!("{} {:.1}%", display_name, percent))
+                        .ratio((*percent as f64 / 100.0).clamp(0.0, 1.0));
+                    frame.render_widget(gauge, lines[0]);
+
+                    let detail = Paragraph::new(Line::from(Span::raw(format!(
+                        "  {speed}  ETA {eta}"
+                    ))))
+                    .style(row_style);
+                    frame.render_widget(detail, lines[1]);
                 }
-                crate::shared::ytdlp::DownloadState::Canceled => ("Canceled".to_string(), ratatui::style::Color::Gray),
+                other => {
+                    let (status, color) = match other {
+                        DownloadState::Queued => ("Queued".to_string(), ratatui::style::Color::Gray),
+                        DownloadState::Completed { .. } => ("Completed".to_string(), ratatui::style::Color::Green),
+                        DownloadState::AlreadyDownloaded { .. } => ("Already Downloaded".to_string(), ratatui::style::Color::Green),
+                        DownloadState::Failed { logs } => {
+                            // Try to find a meaningful error message from the end of the logs
+                            let error = logs
+                                .iter()
+                                .rev()
+                                .find(|line| !line.trim().is_empty() && !line.contains("yt-dlp exited with code"))
+                                .cloned()
+                                .unwrap_or_else(|| "Unknown error".to_string());
+                            (format!("Failed: {}", error), ratatui::style::Color::Red)
+                        }
+                        DownloadState::Canceled => ("Canceled".to_string(), ratatui::style::Color::Gray),
+                        DownloadState::Downloading { .. } => unreachable!(),
+                    };
+
+                    let line = Line::from(vec![
+                        Span::styled(format!("[{}] ", status), Style::default().fg(color)),
+                        Span::raw(display_name),
+                    ]);
+                    frame.render_widget(Paragraph::new(line).style(row_style), *row);
+                }
+            }
+        }
+
+        if let Some(log_view) = &self.log_view {
+            let item = match log_view.backend {
+                Backend::YtDlp => ctx.ytdlp_manager.get(log_view.id),
+                Backend::SpotDl => ctx.spotdl_manager.get(log_view.id),
             };
-            
-            let line = Line::from(vec![
-                Span::styled(format!("[{}] ", status), Style::default().fg(color)),
-                Span::raw(format!("{} : {}", item.inner.id, item.inner.kind)),
-            ]);
-            ListItem::new(line)
-        });
+            match item.as_ref().map(|item| &item.state) {
+                Some(DownloadState::Failed { logs }) => {
+                    let popup_area = centered_rect(80, 70, area);
+                    frame.render_widget(Clear, popup_area);
+                    let block = Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Full log ('j'/'k' scroll, 'q'/Esc close) ");
+                    let inner = block.inner(popup_area);
+                    frame.render_widget(block, popup_area);
 
-        let list_block = Block::default().borders(Borders::ALL).title(" Recent Downloads ");
-        let list = List::new(items).block(list_block);
-        frame.render_widget(list, list_area);
+                    let scroll = log_view.scroll.min(logs.len().saturating_sub(1)) as u16;
+                    let paragraph = Paragraph::new(logs.join("\n")).scroll((scroll, 0));
+                    frame.render_widget(paragraph, inner);
+                }
+                _ => self.log_view = None,
+            }
+        }
 
         Ok(())
     }
 
     fn handle_action(&mut self, event: &mut ActionEvent, ctx: &mut Ctx) -> Result<()> {
+        // The log popup owns the keyboard while open, so 'q'/Esc close it
+        // instead of falling through to the common Quit binding.
+        if self.log_view.is_some() {
+            match event.code() {
+                crossterm::event::KeyCode::Char('q') | crossterm::event::KeyCode::Esc => {
+                    self.log_view = None;
+                }
+                crossterm::event::KeyCode::Char('j') | crossterm::event::KeyCode::Down => {
+                    if let Some(log_view) = &mut self.log_view {
+                        log_view.scroll = log_view.scroll.saturating_add(1);
+                    }
+                }
+                crossterm::event::KeyCode::Char('k') | crossterm::event::KeyCode::Up => {
+                    if let Some(log_view) = &mut self.log_view {
+                        log_view.scroll = log_view.scroll.saturating_sub(1);
+                    }
+                }
+                _ => {}
+            }
+            ctx.render()?;
+            return Ok(());
+        }
+
         // If in insert mode, common actions might be claimed by handle_insert_mode
         // enabling insert mode:
         if let Some(action) = event.claim_common() {
@@ -114,6 +319,114 @@ impl Pane for DownloaderPane {
                 }
                 _ => {}
              }
+            return Ok(());
+        }
+
+        match event.code() {
+            crossterm::event::KeyCode::Char('+') => {
+                let next = ctx.ytdlp_manager.max_concurrent() + 1;
+                ctx.ytdlp_manager.set_max_concurrent(next);
+                ctx.spotdl_manager.set_max_concurrent(next);
+                status_info!("Concurrent download cap set to {}", next);
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('-') => {
+                let next = ctx.ytdlp_manager.max_concurrent().saturating_sub(1).max(1);
+                ctx.ytdlp_manager.set_max_concurrent(next);
+                ctx.spotdl_manager.set_max_concurrent(next);
+                status_info!("Concurrent download cap set to {}", next);
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('f') => {
+                self.format_preset = self.format_preset.next();
+                ctx.config.set_last_format_preset(self.format_preset);
+                status_info!("Format preset: {}", self.format_preset.label());
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('u') => {
+                ctx.ytdlp_manager.update_yt_dlp();
+                status_info!("Fetching latest yt-dlp release...");
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char(']') => {
+                let next = ctx.config.update_check_interval_days() + 1;
+                ctx.config.set_update_check_interval_days(next);
+                status_info!("yt-dlp staleness check interval set to {} day(s)", next);
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('[') => {
+                let next = ctx.config.update_check_interval_days().saturating_sub(1).max(1);
+                ctx.config.set_update_check_interval_days(next);
+                status_info!("yt-dlp staleness check interval set to {} day(s)", next);
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('j') | crossterm::event::KeyCode::Down => {
+                let entries = combined_entries(ctx);
+                if !entries.is_empty() {
+                    let next = (resolve_selected(&entries, self.selected) + 1).min(entries.len() - 1);
+                    let (backend, id, _) = &entries[next];
+                    self.selected = Some((*backend, *id));
+                }
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('k') | crossterm::event::KeyCode::Up => {
+                let entries = combined_entries(ctx);
+                if !entries.is_empty() {
+                    let prev = resolve_selected(&entries, self.selected).saturating_sub(1);
+                    let (backend, id, _) = &entries[prev];
+                    self.selected = Some((*backend, *id));
+                }
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('x') => {
+                let entries = combined_entries(ctx);
+                let idx = resolve_selected(&entries, self.selected);
+                if let Some((backend, id, item)) = entries.get(idx) {
+                    if matches!(item.state, DownloadState::Queued | DownloadState::Downloading { .. }) {
+                        let result = match backend {
+                            Backend::YtDlp => ctx.ytdlp_manager.cancel(*id),
+                            Backend::SpotDl => ctx.spotdl_manager.cancel(*id),
+                        };
+                        match result {
+                            Ok(()) => status_info!("Download canceled"),
+                            Err(e) => status_error!("Failed to cancel download: {}", e),
+                        }
+                    }
+                }
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Char('r') => {
+                let entries = combined_entries(ctx);
+                let idx = resolve_selected(&entries, self.selected);
+                if let Some((backend, id, item)) = entries.get(idx) {
+                    if matches!(item.state, DownloadState::Failed { .. } | DownloadState::Canceled) {
+                        let result = match backend {
+                            Backend::YtDlp => ctx.ytdlp_manager.requeue(*id),
+                            Backend::SpotDl => ctx.spotdl_manager.requeue(*id),
+                        };
+                        match result {
+                            Ok(()) => status_info!("Download requeued"),
+                            Err(e) => status_error!("Failed to requeue download: {}", e),
+                        }
+                    }
+                }
+                ctx.render()?;
+            }
+            crossterm::event::KeyCode::Enter | crossterm::event::KeyCode::Char('l') => {
+                let entries = combined_entries(ctx);
+                let idx = resolve_selected(&entries, self.selected);
+                if let Some((backend, id, item)) = entries.get(idx) {
+                    if matches!(item.state, DownloadState::Failed { .. }) {
+                        self.log_view = Some(LogView {
+                            backend: *backend,
+                            id: *id,
+                            scroll: 0,
+                        });
+                    }
+                }
+                ctx.render()?;
+            }
+            _ => {}
         }
         Ok(())
     }
@@ -123,8 +436,13 @@ impl Pane for DownloaderPane {
             InputResultEvent::Confirm => {
                 let url = ctx.input.value(self.input_id).trim().to_owned();
                 if !url.is_empty() {
-                    match ctx.ytdlp_manager.download_url(&url, None) {
-                        Ok(_) => {
+                    let result = if is_spotify_url(&url) {
+                        ctx.spotdl_manager.download_url(&url).map(|_| ())
+                    } else {
+                        ctx.ytdlp_manager.download_url(&url, &self.format_preset.yt_dlp_args())
+                    };
+                    match result {
+                        Ok(()) => {
                             status_info!("Download started for: {}", url);
                             ctx.input.clear_buffer(self.input_id);
                         }
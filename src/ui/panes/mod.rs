@@ -0,0 +1,12 @@
+use anyhow::Result;
+
+use crate::{ctx::Ctx, shared::keys::ActionEvent, ui::input::InputResultEvent};
+
+pub mod downloader;
+
+/// A single screen/tab in the TUI.
+pub trait Pane {
+    fn render(&mut self, frame: &mut ratatui::Frame, area: ratatui::layout::Rect, ctx: &Ctx) -> Result<()>;
+    fn handle_action(&mut self, event: &mut ActionEvent, ctx: &mut Ctx) -> Result<()>;
+    fn handle_insert_mode(&mut self, kind: InputResultEvent, ctx: &mut Ctx) -> Result<()>;
+}